@@ -5,28 +5,103 @@ const WEIGHT_INIT_MIN: f64 = -1.0;
 const WEIGHT_INIT_MAX: f64 = 1.0;
 const SIGMOID_ONE: f64 = 1.0;
 
+fn sigmoid(z: f64) -> f64 {
+    SIGMOID_ONE / (SIGMOID_ONE + (-z).exp())
+}
+
+fn sigmoid_derivative(output: f64) -> f64 {
+    output * (SIGMOID_ONE - output)
+}
+
+fn tanh_derivative(output: f64) -> f64 {
+    SIGMOID_ONE - output * output
+}
+
+fn relu(z: f64) -> f64 {
+    z.max(0.0)
+}
+
+fn relu_derivative(output: f64) -> f64 {
+    if output > 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// A nonlinearity paired with its derivative, so `BrainFusionNet` never has
+/// to assume which one is in use.
+///
+/// `df` takes the *activation output*, not the pre-activation sum, since
+/// that is what `Neuron::activate` caches and what backpropagation already
+/// has on hand.
+#[derive(Debug, Clone, Copy)]
+pub struct Activation {
+    pub f: fn(f64) -> f64,
+    pub df: fn(f64) -> f64,
+}
+
+impl Activation {
+    pub fn sigmoid() -> Self {
+        Activation { f: sigmoid, df: sigmoid_derivative }
+    }
+
+    pub fn tanh() -> Self {
+        Activation { f: f64::tanh, df: tanh_derivative }
+    }
+
+    pub fn relu() -> Self {
+        Activation { f: relu, df: relu_derivative }
+    }
+}
+
+impl Default for Activation {
+    fn default() -> Self {
+        Self::sigmoid()
+    }
+}
+
+/// Tunable knobs for `BrainFusionNet::backpropagate`: momentum and L2
+/// weight decay.
+#[derive(Debug, Clone, Copy)]
+pub struct LearningParameters {
+    pub momentum: f64,
+    pub weight_decay: f64,
+}
+
+impl Default for LearningParameters {
+    fn default() -> Self {
+        LearningParameters { momentum: 0.0, weight_decay: 0.0 }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Neuron {
     weights: Vec<f64>,
     bias: f64,
     activation: f64,
     delta: f64,
+    activation_fn: Activation,
+    prev_delta_weights: Vec<f64>,
+    prev_delta_bias: f64,
 }
 
 impl Neuron {
     /// Creates a neuron with randomized weights sized to the input vector.
-    pub fn new(input_size: usize) -> Self {
+    pub fn new(input_size: usize, activation_fn: Activation) -> Self {
         let mut rng = thread_rng();
         let uniform = Uniform::from(WEIGHT_INIT_MIN..WEIGHT_INIT_MAX);
         let weights: Vec<f64> = (0..input_size).map(|_| uniform.sample(&mut rng)).collect();
         let bias = uniform.sample(&mut rng);
-        Neuron { weights, bias, activation: 0.0, delta: 0.0 }
+        let prev_delta_weights = vec![0.0; input_size];
+        Neuron { weights, bias, activation: 0.0, delta: 0.0, activation_fn, prev_delta_weights, prev_delta_bias: 0.0 }
     }
 
-    /// Computes the sigmoid activation for the provided inputs.
+    /// Computes the neuron's activation for the provided inputs using its
+    /// configured `Activation`.
     pub fn activate(&mut self, inputs: &[f64]) -> f64 {
-        self.activation = inputs.iter().zip(self.weights.iter()).map(|(&i, &w)| i * w).sum::<f64>() + self.bias;
-        self.activation = SIGMOID_ONE / (SIGMOID_ONE + (-self.activation).exp());
+        let z = inputs.iter().zip(self.weights.iter()).map(|(&i, &w)| i * w).sum::<f64>() + self.bias;
+        self.activation = (self.activation_fn.f)(z);
         self.activation
     }
 
@@ -42,17 +117,26 @@ impl Neuron {
 #[derive(Debug, Clone)]
 pub struct BrainFusionNet {
     layers: Vec<Vec<Neuron>>,
+    learning_parameters: LearningParameters,
 }
 
 impl BrainFusionNet {
-    /// Builds a multi-layer network given the number of neurons per layer.
-    pub fn new(layer_sizes: &[usize]) -> Self {
+    /// Sets the momentum and weight-decay used by subsequent `backpropagate` calls.
+    pub fn set_learning_parameters(&mut self, learning_parameters: LearningParameters) {
+        self.learning_parameters = learning_parameters;
+    }
+    /// Builds a multi-layer network given the number of neurons per layer,
+    /// using `hidden_activation` for every layer but the last and
+    /// `output_activation` for the output layer.
+    pub fn new(layer_sizes: &[usize], hidden_activation: Activation, output_activation: Activation) -> Self {
         let mut layers = Vec::new();
+        let output_layer = layer_sizes.len().saturating_sub(2);
         for i in 1..layer_sizes.len() {
-            let layer: Vec<Neuron> = (0..layer_sizes[i]).map(|_| Neuron::new(layer_sizes[i-1])).collect();
+            let activation_fn = if i - 1 == output_layer { output_activation } else { hidden_activation };
+            let layer: Vec<Neuron> = (0..layer_sizes[i]).map(|_| Neuron::new(layer_sizes[i - 1], activation_fn)).collect();
             layers.push(layer);
         }
-        BrainFusionNet { layers }
+        BrainFusionNet { layers, learning_parameters: LearningParameters::default() }
     }
 
     /// Runs a forward pass and returns the output activations.
@@ -92,23 +176,29 @@ impl BrainFusionNet {
         let output_layer = self.layers.len() - 1;
         for (i, neuron) in self.layers[output_layer].iter_mut().enumerate() {
             let error = targets[i] - outputs[i];
-            neuron.delta = error * outputs[i] * (SIGMOID_ONE - outputs[i]);
+            neuron.delta = error * (neuron.activation_fn.df)(outputs[i]);
         }
 
         for l in (0..output_layer).rev() {
             for (i, neuron) in self.layers[l].iter_mut().enumerate() {
                 let error: f64 = self.layers[l+1].iter().map(|n| n.delta * n.weights[i]).sum();
-                neuron.delta = error * neuron.activation * (SIGMOID_ONE - neuron.activation);
+                neuron.delta = error * (neuron.activation_fn.df)(neuron.activation);
             }
         }
 
+        let momentum = self.learning_parameters.momentum;
+        let weight_decay = self.learning_parameters.weight_decay;
         for (l, layer) in self.layers.iter_mut().enumerate() {
             let prev = if l == 0 { inputs } else { &activations[l-1] };
             for neuron in layer.iter_mut() {
-                for (w, &input) in neuron.weights.iter_mut().zip(prev.iter()) {
-                    *w += learning_rate * neuron.delta * input;
+                for (idx, (w, &input)) in neuron.weights.iter_mut().zip(prev.iter()).enumerate() {
+                    let dw = learning_rate * neuron.delta * input + momentum * neuron.prev_delta_weights[idx] - weight_decay * *w;
+                    *w += dw;
+                    neuron.prev_delta_weights[idx] = dw;
                 }
-                neuron.bias += learning_rate * neuron.delta;
+                let db = learning_rate * neuron.delta + momentum * neuron.prev_delta_bias;
+                neuron.bias += db;
+                neuron.prev_delta_bias = db;
             }
         }
     }
@@ -1,5 +1,5 @@
 use rand::distributions::{Distribution, Uniform};
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 
 const DEFAULT_MEMBRANE_POTENTIAL: f64 = 0.0;
 const DEFAULT_THRESHOLD: f64 = 1.0;
@@ -11,6 +11,7 @@ const DEFAULT_A_PLUS: f64 = 0.01;
 const DEFAULT_A_MINUS: f64 = -0.012;
 const DEFAULT_TAU_PLUS: f64 = 20.0;
 const DEFAULT_TAU_MINUS: f64 = 20.0;
+const DEFAULT_TAU_E: f64 = 20.0;
 const DEFAULT_LEARNING_RATE: f64 = 0.001;
 const DEFAULT_MIN_WEIGHT: f64 = -1.0;
 const DEFAULT_MAX_WEIGHT: f64 = 1.0;
@@ -18,6 +19,42 @@ const DEFAULT_WEIGHT_MIN: f64 = -0.1;
 const DEFAULT_WEIGHT_MAX: f64 = 0.1;
 const INPUT_SPIKE_MAGNITUDE: f64 = 1.0;
 
+const IZHIKEVICH_PEAK_POTENTIAL: f64 = 30.0;
+const IZHIKEVICH_RS_A: f64 = 0.02;
+const IZHIKEVICH_RS_B: f64 = 0.2;
+const IZHIKEVICH_RS_C: f64 = -65.0;
+const IZHIKEVICH_RS_D: f64 = 8.0;
+
+const HH_MEMBRANE_CAPACITANCE: f64 = 1.0;
+const HH_G_NA: f64 = 120.0;
+const HH_G_K: f64 = 36.0;
+const HH_G_LEAK: f64 = 0.3;
+const HH_E_NA: f64 = 50.0;
+const HH_E_K: f64 = -77.0;
+const HH_E_LEAK: f64 = -54.4;
+const HH_RESTING_POTENTIAL: f64 = -65.0;
+const HH_SPIKE_THRESHOLD: f64 = 0.0;
+const HH_RATE_SINGULARITY_EPSILON: f64 = 1e-6;
+
+/// Common interface for single-neuron dynamics driven by an input current.
+///
+/// Implementors own their membrane state and decide internally what counts as
+/// a spike; `SpikingNet`/`LoihiEmulator` only ever talk to neurons through
+/// this trait, which is what lets a network be simulated with LIF,
+/// Izhikevich, or Hodgkin-Huxley cells interchangeably.
+pub trait NeuronModel {
+    /// Integrates the neuron state over a time step and updates spike status.
+    fn integrate(&mut self, input_current: f64, dt: f64, current_time: f64);
+    /// Returns whether the neuron spiked during the most recent `integrate` call.
+    fn spiked(&self) -> bool;
+    /// Returns the simulation time of the most recent spike, or a negative
+    /// sentinel if the neuron has never spiked.
+    fn last_spike_time(&self) -> f64;
+    /// Returns the neuron's primary membrane-potential-like state variable,
+    /// for monitoring and plotting.
+    fn potential(&self) -> f64;
+}
+
 #[derive(Debug, Clone)]
 pub struct LifNeuron {
     membrane_potential: f64,
@@ -45,60 +82,446 @@ impl LifNeuron {
         }
     }
 
-    /// Integrates the neuron state over a time step and updates spike status.
-    pub fn integrate(&mut self, input_current: f64, dt: f64, current_time: f64) {
+    /// Computes how far before the end of the step the membrane potential
+    /// actually crossed `threshold`, for a constant input current over `dt`.
+    ///
+    /// Solves the LIF equation's exponential approach to `v_inf = tau *
+    /// input_current` for the crossing time `dt_cross`, then returns the
+    /// offset `dt - dt_cross` so callers can backdate the spike time.
+    fn crossing_offset(&self, input_current: f64, dt: f64, v_prev: f64) -> f64 {
+        let v_inf = self.tau * input_current;
+        let ratio = (v_inf - self.threshold) / (v_inf - v_prev);
+        if !ratio.is_finite() || ratio <= 0.0 {
+            return 0.0;
+        }
+        let dt_cross = -self.tau * ratio.ln();
+        (dt - dt_cross).clamp(0.0, dt)
+    }
+}
+
+impl Default for LifNeuron {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NeuronModel for LifNeuron {
+    fn integrate(&mut self, input_current: f64, dt: f64, current_time: f64) {
         self.time_since_spike += dt;
         if self.time_since_spike < self.refractory_period {
             self.membrane_potential = self.reset_potential;
             self.spiked = false;
             return;
         }
+        let v_prev = self.membrane_potential;
         self.membrane_potential += dt * (-self.membrane_potential / self.tau + input_current);
         self.spiked = if self.membrane_potential >= self.threshold {
+            self.last_spike_time = current_time - self.crossing_offset(input_current, dt, v_prev);
             self.membrane_potential = self.reset_potential;
             self.time_since_spike = 0.0;
+            true
+        } else {
+            false
+        };
+    }
+
+    fn spiked(&self) -> bool {
+        self.spiked
+    }
+
+    fn last_spike_time(&self) -> f64 {
+        self.last_spike_time
+    }
+
+    fn potential(&self) -> f64 {
+        self.membrane_potential
+    }
+}
+
+/// Izhikevich two-variable neuron (`v`, `u`) following Izhikevich (2003).
+#[derive(Debug, Clone)]
+pub struct IzhikevichNeuron {
+    v: f64,
+    u: f64,
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    last_spike_time: f64,
+    spiked: bool,
+}
+
+impl IzhikevichNeuron {
+    /// Creates an Izhikevich neuron with the given `a`, `b`, `c`, `d` parameters.
+    pub fn new(a: f64, b: f64, c: f64, d: f64) -> Self {
+        IzhikevichNeuron {
+            v: c,
+            u: b * c,
+            a,
+            b,
+            c,
+            d,
+            last_spike_time: DEFAULT_LAST_SPIKE_TIME,
+            spiked: false,
+        }
+    }
+
+    /// Creates a regular-spiking Izhikevich neuron (`a=0.02, b=0.2, c=-65, d=8`).
+    pub fn new_regular_spiking() -> Self {
+        Self::new(IZHIKEVICH_RS_A, IZHIKEVICH_RS_B, IZHIKEVICH_RS_C, IZHIKEVICH_RS_D)
+    }
+}
+
+impl Default for IzhikevichNeuron {
+    fn default() -> Self {
+        Self::new_regular_spiking()
+    }
+}
+
+impl NeuronModel for IzhikevichNeuron {
+    fn integrate(&mut self, input_current: f64, dt: f64, current_time: f64) {
+        self.v += dt * (0.04 * self.v * self.v + 5.0 * self.v + 140.0 - self.u + input_current);
+        self.u += dt * self.a * (self.b * self.v - self.u);
+        self.spiked = if self.v >= IZHIKEVICH_PEAK_POTENTIAL {
+            self.v = self.c;
+            self.u += self.d;
             self.last_spike_time = current_time;
             true
         } else {
             false
         };
     }
+
+    fn spiked(&self) -> bool {
+        self.spiked
+    }
+
+    fn last_spike_time(&self) -> f64 {
+        self.last_spike_time
+    }
+
+    fn potential(&self) -> f64 {
+        self.v
+    }
+}
+
+fn hh_alpha_m(v: f64) -> f64 {
+    let x = v + 40.0;
+    if x.abs() < HH_RATE_SINGULARITY_EPSILON {
+        return 1.0;
+    }
+    0.1 * x / (1.0 - (-x / 10.0).exp())
+}
+
+fn hh_beta_m(v: f64) -> f64 {
+    4.0 * (-(v + 65.0) / 18.0).exp()
 }
 
+fn hh_alpha_h(v: f64) -> f64 {
+    0.07 * (-(v + 65.0) / 20.0).exp()
+}
+
+fn hh_beta_h(v: f64) -> f64 {
+    1.0 / (1.0 + (-(v + 35.0) / 10.0).exp())
+}
+
+fn hh_alpha_n(v: f64) -> f64 {
+    let x = v + 55.0;
+    if x.abs() < HH_RATE_SINGULARITY_EPSILON {
+        return 0.1;
+    }
+    0.01 * x / (1.0 - (-x / 10.0).exp())
+}
+
+fn hh_beta_n(v: f64) -> f64 {
+    0.125 * (-(v + 65.0) / 80.0).exp()
+}
+
+/// Hodgkin-Huxley neuron integrating the classic sodium/potassium/leak
+/// conductance model via the `m`, `h`, `n` gating variables.
 #[derive(Debug, Clone)]
-pub struct SpikingNet {
-    neurons: Vec<LifNeuron>,
-    weights: Vec<Vec<f64>>,
+pub struct HodgkinHuxleyNeuron {
+    v: f64,
+    m: f64,
+    h: f64,
+    n: f64,
+    last_spike_time: f64,
+    spiked: bool,
+}
+
+impl HodgkinHuxleyNeuron {
+    /// Creates a Hodgkin-Huxley neuron at resting potential with gating
+    /// variables initialized to their steady-state values.
+    pub fn new() -> Self {
+        let v = HH_RESTING_POTENTIAL;
+        let m = hh_alpha_m(v) / (hh_alpha_m(v) + hh_beta_m(v));
+        let h = hh_alpha_h(v) / (hh_alpha_h(v) + hh_beta_h(v));
+        let n = hh_alpha_n(v) / (hh_alpha_n(v) + hh_beta_n(v));
+        HodgkinHuxleyNeuron {
+            v,
+            m,
+            h,
+            n,
+            last_spike_time: DEFAULT_LAST_SPIKE_TIME,
+            spiked: false,
+        }
+    }
+}
+
+impl Default for HodgkinHuxleyNeuron {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NeuronModel for HodgkinHuxleyNeuron {
+    fn integrate(&mut self, input_current: f64, dt: f64, current_time: f64) {
+        let v = self.v;
+        self.m += dt * (hh_alpha_m(v) * (1.0 - self.m) - hh_beta_m(v) * self.m);
+        self.h += dt * (hh_alpha_h(v) * (1.0 - self.h) - hh_beta_h(v) * self.h);
+        self.n += dt * (hh_alpha_n(v) * (1.0 - self.n) - hh_beta_n(v) * self.n);
+
+        let i_na = HH_G_NA * self.m.powi(3) * self.h * (v - HH_E_NA);
+        let i_k = HH_G_K * self.n.powi(4) * (v - HH_E_K);
+        let i_leak = HH_G_LEAK * (v - HH_E_LEAK);
+        let dv = (input_current - i_na - i_k - i_leak) / HH_MEMBRANE_CAPACITANCE;
+        let new_v = v + dt * dv;
+
+        self.spiked = new_v >= HH_SPIKE_THRESHOLD && v < HH_SPIKE_THRESHOLD;
+        if self.spiked {
+            self.last_spike_time = current_time;
+        }
+        self.v = new_v;
+    }
+
+    fn spiked(&self) -> bool {
+        self.spiked
+    }
+
+    fn last_spike_time(&self) -> f64 {
+        self.last_spike_time
+    }
+
+    fn potential(&self) -> f64 {
+        self.v
+    }
+}
+
+/// Records `(neuron_idx, sim_time)` spike events as a network runs.
+#[derive(Debug, Clone, Default)]
+pub struct SpikeMonitor {
+    events: Vec<(usize, f64)>,
+}
+
+impl SpikeMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&mut self, spikes: &[bool], sim_time: f64) {
+        for (idx, &spiked) in spikes.iter().enumerate() {
+            if spiked {
+                self.events.push((idx, sim_time));
+            }
+        }
+    }
+
+    /// Returns the recorded `(neuron_idx, sim_time)` spike events in order.
+    pub fn events(&self) -> &[(usize, f64)] {
+        &self.events
+    }
+}
+
+/// Records per-neuron membrane-potential traces for a selected set of indices.
+#[derive(Debug, Clone)]
+pub struct StateMonitor {
+    tracked_indices: Vec<usize>,
+    traces: Vec<Vec<f64>>,
+}
+
+impl StateMonitor {
+    pub fn new(tracked_indices: Vec<usize>) -> Self {
+        let traces = vec![Vec::new(); tracked_indices.len()];
+        Self { tracked_indices, traces }
+    }
+
+    fn record(&mut self, potentials: &[f64]) {
+        for (slot, &idx) in self.tracked_indices.iter().enumerate() {
+            if let Some(&value) = potentials.get(idx) {
+                self.traces[slot].push(value);
+            }
+        }
+    }
+
+    /// Returns the recorded potential trace for `neuron_idx`, if it is tracked.
+    pub fn trace(&self, neuron_idx: usize) -> Option<&[f64]> {
+        self.tracked_indices
+            .iter()
+            .position(|&tracked| tracked == neuron_idx)
+            .map(|slot| self.traces[slot].as_slice())
+    }
+}
+
+/// Records the fraction of neurons spiking each step.
+#[derive(Debug, Clone, Default)]
+pub struct PopulationRateMonitor {
+    rates: Vec<f64>,
+}
+
+impl PopulationRateMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&mut self, spikes: &[bool]) {
+        let fraction = if spikes.is_empty() {
+            0.0
+        } else {
+            spikes.iter().filter(|&&spiked| spiked).count() as f64 / spikes.len() as f64
+        };
+        self.rates.push(fraction);
+    }
+
+    /// Returns the recorded per-step spiking fraction series.
+    pub fn rates(&self) -> &[f64] {
+        &self.rates
+    }
+
+    /// Reports the population firing rate in Hz, averaged over the last
+    /// `window` recorded steps of size `dt`. `dt` is in milliseconds, the
+    /// same unit used everywhere else in this module (e.g. `DEFAULT_TAU`),
+    /// so it converts to seconds internally before computing the rate.
+    pub fn windowed_rate_hz(&self, dt: f64, window: usize) -> f64 {
+        if dt <= 0.0 || self.rates.is_empty() {
+            return 0.0;
+        }
+        let window = window.min(self.rates.len()).max(1);
+        let recent = &self.rates[self.rates.len() - window..];
+        let mean_fraction = recent.iter().sum::<f64>() / recent.len() as f64;
+        let dt_seconds = dt / 1000.0;
+        mean_fraction / dt_seconds
+    }
+}
+
+/// A single directed synaptic connection from neuron `pre` to neuron `post`.
+#[derive(Debug, Clone)]
+pub struct Synapse {
+    pub pre: usize,
+    pub post: usize,
+    pub weight: f64,
+    /// STDP eligibility trace, decayed each step and committed to `weight`
+    /// only when `SpikingNet::apply_reward` is called (reward-modulated STDP).
+    pub eligibility: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpikingNet<M: NeuronModel> {
+    neurons: Vec<M>,
+    synapses: Vec<Synapse>,
+    /// For each neuron, the indices into `synapses` of connections targeting it.
+    incoming: Vec<Vec<usize>>,
     a_plus: f64,
     a_minus: f64,
     tau_plus: f64,
     tau_minus: f64,
+    tau_e: f64,
     learning_rate: f64,
     min_weight: f64,
     max_weight: f64,
     sim_time: f64,
+    spike_monitor: Option<SpikeMonitor>,
+    state_monitor: Option<StateMonitor>,
+    rate_monitor: Option<PopulationRateMonitor>,
 }
 
-impl SpikingNet {
+impl<M: NeuronModel + Default + Clone> SpikingNet<M> {
     /// Builds a fully connected spiking network with randomized weights.
     pub fn new(num_neurons: usize) -> Self {
         let mut rng = thread_rng();
         let uniform = Uniform::from(DEFAULT_WEIGHT_MIN..DEFAULT_WEIGHT_MAX);
-        let weights: Vec<Vec<f64>> = (0..num_neurons).map(|_| (0..num_neurons).map(|_| uniform.sample(&mut rng)).collect()).collect();
-        let neurons = (0..num_neurons).map(|_| LifNeuron::new()).collect();
+        let synapses: Vec<Synapse> = (0..num_neurons)
+            .flat_map(|post| (0..num_neurons).map(move |pre| (post, pre)))
+            .map(|(post, pre)| Synapse { pre, post, weight: uniform.sample(&mut rng), eligibility: 0.0 })
+            .collect();
+        Self::from_parts(num_neurons, synapses)
+    }
+
+    /// Builds a sparsely connected network, wiring each ordered `(pre, post)`
+    /// pair with the given `probability` (self-connections are forbidden).
+    pub fn with_connectivity(num_neurons: usize, probability: f64, rng: &mut impl Rng) -> Self {
+        let uniform = Uniform::from(DEFAULT_WEIGHT_MIN..DEFAULT_WEIGHT_MAX);
+        let mut synapses = Vec::new();
+        for post in 0..num_neurons {
+            for pre in 0..num_neurons {
+                if pre == post {
+                    continue;
+                }
+                if rng.gen::<f64>() < probability {
+                    synapses.push(Synapse { pre, post, weight: uniform.sample(rng), eligibility: 0.0 });
+                }
+            }
+        }
+        Self::from_parts(num_neurons, synapses)
+    }
+
+    fn from_parts(num_neurons: usize, synapses: Vec<Synapse>) -> Self {
+        let mut incoming = vec![Vec::new(); num_neurons];
+        for (idx, synapse) in synapses.iter().enumerate() {
+            incoming[synapse.post].push(idx);
+        }
+        let neurons = (0..num_neurons).map(|_| M::default()).collect();
         SpikingNet {
             neurons,
-            weights,
+            synapses,
+            incoming,
             a_plus: DEFAULT_A_PLUS,
             a_minus: DEFAULT_A_MINUS,
             tau_plus: DEFAULT_TAU_PLUS,
             tau_minus: DEFAULT_TAU_MINUS,
+            tau_e: DEFAULT_TAU_E,
             learning_rate: DEFAULT_LEARNING_RATE,
             min_weight: DEFAULT_MIN_WEIGHT,
             max_weight: DEFAULT_MAX_WEIGHT,
             sim_time: 0.0,
+            spike_monitor: None,
+            state_monitor: None,
+            rate_monitor: None,
         }
     }
+}
+
+impl<M: NeuronModel> SpikingNet<M> {
+    /// Registers a spike monitor that records every spike going forward.
+    pub fn attach_spike_monitor(&mut self) {
+        self.spike_monitor = Some(SpikeMonitor::new());
+    }
+
+    /// Registers a state monitor that records membrane potentials for
+    /// `neuron_indices` on every step going forward.
+    pub fn attach_state_monitor(&mut self, neuron_indices: Vec<usize>) {
+        self.state_monitor = Some(StateMonitor::new(neuron_indices));
+    }
+
+    /// Registers a population rate monitor that records the spiking fraction
+    /// of the network on every step going forward.
+    pub fn attach_population_rate_monitor(&mut self) {
+        self.rate_monitor = Some(PopulationRateMonitor::new());
+    }
+
+    /// Returns the attached spike monitor, if any.
+    pub fn spike_monitor(&self) -> Option<&SpikeMonitor> {
+        self.spike_monitor.as_ref()
+    }
+
+    /// Returns the attached state monitor, if any.
+    pub fn state_monitor(&self) -> Option<&StateMonitor> {
+        self.state_monitor.as_ref()
+    }
+
+    /// Returns the attached population rate monitor, if any.
+    pub fn rate_monitor(&self) -> Option<&PopulationRateMonitor> {
+        self.rate_monitor.as_ref()
+    }
 
     /// Advances the network by a time step and returns neuron spike events.
     pub fn step(&mut self, inputs: &[f64], dt: f64) -> Vec<bool> {
@@ -108,50 +531,77 @@ impl SpikingNet {
         for (index, &input) in inputs.iter().enumerate().take(neuron_count) {
             currents[index] = input;
         }
-        for i in 0..neuron_count {
-            for j in 0..neuron_count {
-                if self.neurons[j].spiked {
-                    currents[i] += self.weights[i][j];
+        for post in 0..neuron_count {
+            for &syn_idx in &self.incoming[post] {
+                let synapse = &self.synapses[syn_idx];
+                if self.neurons[synapse.pre].spiked() {
+                    currents[post] += synapse.weight;
                 }
             }
         }
         let mut spikes = vec![false; neuron_count];
         for (i, neuron) in self.neurons.iter_mut().enumerate() {
             neuron.integrate(currents[i], dt, self.sim_time);
-            spikes[i] = neuron.spiked;
+            spikes[i] = neuron.spiked();
+        }
+        self.apply_stdp(dt);
+        if let Some(monitor) = self.spike_monitor.as_mut() {
+            monitor.record(&spikes, self.sim_time);
+        }
+        if let Some(monitor) = self.state_monitor.as_mut() {
+            let potentials: Vec<f64> = self.neurons.iter().map(|neuron| neuron.potential()).collect();
+            monitor.record(&potentials);
+        }
+        if let Some(monitor) = self.rate_monitor.as_mut() {
+            monitor.record(&spikes);
         }
-        self.apply_stdp();
         spikes
     }
 
-    fn apply_stdp(&mut self) {
-        for post_idx in 0..self.neurons.len() {
-            for pre_idx in 0..self.neurons.len() {
-                let post_time = self.neurons[post_idx].last_spike_time;
-                let pre_time = self.neurons[pre_idx].last_spike_time;
-                if post_time < 0.0 || pre_time < 0.0 {
-                    continue;
-                }
+    /// Updates each synapse's eligibility trace: decays it over `dt`, then
+    /// adds the usual Hebbian STDP `delta_w` for the current pre/post spike
+    /// timing. This is three-factor learning's first two factors; the third
+    /// (reward) is applied separately via `apply_reward`.
+    fn apply_stdp(&mut self, dt: f64) {
+        let decay = (-dt / self.tau_e).exp();
+        for idx in 0..self.synapses.len() {
+            let (pre, post) = (self.synapses[idx].pre, self.synapses[idx].post);
+            let mut eligibility = self.synapses[idx].eligibility * decay;
+            let post_time = self.neurons[post].last_spike_time();
+            let pre_time = self.neurons[pre].last_spike_time();
+            if post_time >= 0.0 && pre_time >= 0.0 {
                 let delta_t = post_time - pre_time;
                 let delta_w = if delta_t > 0.0 {
                     self.a_plus * (-delta_t / self.tau_plus).exp()
                 } else {
                     self.a_minus * (delta_t / self.tau_minus).exp()
                 };
-                let updated = self.weights[post_idx][pre_idx] + self.learning_rate * delta_w;
-                self.weights[post_idx][pre_idx] = updated.clamp(self.min_weight, self.max_weight);
+                eligibility += delta_w;
             }
+            self.synapses[idx].eligibility = eligibility;
+        }
+    }
+
+    /// Commits eligibility-traced weight changes scaled by a scalar `reward`
+    /// signal, i.e. `w += learning_rate * reward * eligibility`, clamped to
+    /// `[min_weight, max_weight]`. This lets callers drive reinforcement
+    /// learning without relying purely on spike-timing correlations.
+    pub fn apply_reward(&mut self, reward: f64) {
+        let (learning_rate, min_weight, max_weight) = (self.learning_rate, self.min_weight, self.max_weight);
+        for synapse in self.synapses.iter_mut() {
+            let updated = synapse.weight + learning_rate * reward * synapse.eligibility;
+            synapse.weight = updated.clamp(min_weight, max_weight);
         }
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct LoihiEmulator {
-    net: SpikingNet,
+pub struct LoihiEmulator<M: NeuronModel> {
+    net: SpikingNet<M>,
     pending_spikes: Vec<(usize, f64)>,
 }
 
-impl LoihiEmulator {
+impl<M: NeuronModel + Default + Clone> LoihiEmulator<M> {
     /// Creates a Loihi-style emulator backed by a spiking network.
     pub fn new(num_neurons: usize) -> Self {
         Self {
@@ -159,7 +609,9 @@ impl LoihiEmulator {
             pending_spikes: Vec::new(),
         }
     }
+}
 
+impl<M: NeuronModel> LoihiEmulator<M> {
     /// Queues an external spike for injection into the next simulation step.
     pub fn inject_spike(&mut self, neuron_idx: usize, time: f64) {
         self.pending_spikes.push((neuron_idx, time));
@@ -177,7 +629,7 @@ impl LoihiEmulator {
     }
 
     /// Returns an immutable view of the underlying spiking network.
-    pub fn network(&self) -> &SpikingNet {
+    pub fn network(&self) -> &SpikingNet<M> {
         &self.net
     }
 }